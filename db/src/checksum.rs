@@ -0,0 +1,91 @@
+//! Per-value integrity checksums. Values are stored with a trailing CRC32C so
+//! silent in-memory corruption in the long-lived `User`/`Table` maps can be
+//! caught when the value is read back, and so clients can validate the value
+//! end-to-end.
+
+/// The CRC32C (Castagnoli) algorithm identifier carried in the wireformat so a
+/// client knows how the checksum was computed. Algorithm id `0` means no
+/// checksum was supplied.
+pub const ALGORITHM_CRC32C: u8 = 1;
+
+/// The number of bytes a stored CRC32C checksum occupies when appended to a
+/// value.
+pub const CHECKSUM_LEN: usize = 4;
+
+// The Castagnoli polynomial, reflected, used by CRC32C.
+const POLYNOMIAL: u32 = 0x82f6_3b78;
+
+/// Computes the CRC32C of a byte slice, using the hardware `crc32` instruction
+/// where the CPU supports SSE 4.2 and falling back to a software
+/// implementation otherwise.
+pub fn crc32c(data: &[u8]) -> u32 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("sse4.2") {
+            // Safety: guarded by the runtime feature check above.
+            return unsafe { crc32c_hw(data) };
+        }
+    }
+
+    crc32c_sw(data)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.2")]
+unsafe fn crc32c_hw(data: &[u8]) -> u32 {
+    use std::arch::x86_64::_mm_crc32_u8;
+
+    let mut crc: u32 = !0;
+    for byte in data {
+        crc = _mm_crc32_u8(crc, *byte);
+    }
+    !crc
+}
+
+// Software CRC32C, used when the hardware instruction is unavailable. Computed
+// bitwise against the reflected Castagnoli polynomial so no lookup table needs
+// to be carried.
+fn crc32c_sw(data: &[u8]) -> u32 {
+    let mut crc: u32 = !0;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (POLYNOMIAL & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The canonical CRC32C check value for the ASCII string "123456789".
+    #[test]
+    fn known_check_vector() {
+        assert_eq!(crc32c(b"123456789"), 0xe306_9283);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(crc32c(b""), 0);
+    }
+
+    // The hardware and software paths must produce identical digests so a node
+    // that computed a checksum on one CPU can be verified on another.
+    #[test]
+    fn hardware_and_software_agree() {
+        let data = b"the quick brown fox jumps over the lazy dog";
+        assert_eq!(crc32c(data), crc32c_sw(data));
+    }
+
+    // A single flipped bit must change the digest, so corruption is detected.
+    #[test]
+    fn detects_single_bit_flip() {
+        let mut data = b"checksum-guarded value".to_vec();
+        let original = crc32c(&data);
+        data[0] ^= 0x01;
+        assert_ne!(crc32c(&data), original);
+    }
+}
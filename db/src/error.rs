@@ -0,0 +1,35 @@
+use super::wireformat::RpcStatus;
+
+/// A categorized view of why a dispatch outcome failed. The flat `RpcStatus`
+/// enum carries one variant per concrete condition; this taxonomy groups them
+/// into the five families a client actually branches on — a transient internal
+/// fault, a request it must fix, a lookup that found nothing, a permission
+/// failure, and an opcode the service does not implement — so handlers can report a failure by category without each site
+/// picking a status by hand.
+#[derive(Clone, Copy)]
+pub enum ErrorCategory {
+    /// The server failed to service an otherwise valid request.
+    Internal,
+    /// The request could not be parsed or violated the wireformat.
+    Malformed,
+    /// The request named a tenant, table, or object that does not exist.
+    NotFound,
+    /// The issuing tenant is not permitted to perform the operation.
+    Unauthorized,
+    /// The request carried an opcode the service does not implement.
+    UnknownOpcode,
+}
+
+impl ErrorCategory {
+    /// Maps a category onto the concrete `RpcStatus` written into a response
+    /// header.
+    pub fn status(self) -> RpcStatus {
+        match self {
+            ErrorCategory::Internal => RpcStatus::StatusInternalError,
+            ErrorCategory::Malformed => RpcStatus::StatusMalformedRequest,
+            ErrorCategory::NotFound => RpcStatus::StatusObjectDoesNotExist,
+            ErrorCategory::Unauthorized => RpcStatus::StatusTenantDoesNotExist,
+            ErrorCategory::UnknownOpcode => RpcStatus::StatusUnknownOpcode,
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! The Sandstorm storage node. This crate implements the `Master` service —
+//! the in-memory key-value store, its RPC wireformat, and the distribution
+//! subsystems (consistent-hashing ring, quorum replication, cluster
+//! membership, latency metrics, and per-value checksums) layered on top of it.
+
+#[macro_use]
+extern crate log;
+
+extern crate arc_swap;
+extern crate bytes;
+extern crate e2d2;
+extern crate sandstorm;
+
+pub mod common;
+pub mod wireformat;
+pub mod rpc;
+pub mod service;
+pub mod table;
+pub mod ext;
+
+pub mod ring;
+pub mod quorum;
+pub mod membership;
+pub mod metrics;
+pub mod checksum;
+pub mod error;
+
+pub mod master;
@@ -1,6 +1,14 @@
 use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::{Arc, Mutex};
 
+use super::checksum::{crc32c, ALGORITHM_CRC32C, CHECKSUM_LEN};
+use super::error::ErrorCategory;
 use super::ext::*;
+use super::membership::{discover_consul, System};
+use super::metrics::{Metrics, OpSnapshot};
+use super::quorum::Quorum;
+use super::ring::{NodeId, Ring};
 use super::table::*;
 use super::wireformat::*;
 use super::service::Service;
@@ -12,8 +20,34 @@ use e2d2::headers::UdpHeader;
 use e2d2::common::EmptyMetadata;
 
 use sandstorm::null::NullDB;
+use arc_swap::ArcSwap;
 use bytes::{Bytes, BytesMut, BufMut};
 
+// The number of replicas each partition is placed on. A single-node store
+// places every partition on its only node.
+const REPLICATION_FACTOR: usize = 3;
+
+// The file the active peer set is persisted to so a full-cluster restart can
+// bootstrap the ring without Consul being reachable.
+const PEERS_PATH: &str = "master.peers";
+
+// Serializes a single multi-get result onto the response buffer as a one-byte
+// status, a four-byte little-endian value length, and the value bytes.
+fn push_result(buf: &mut BytesMut, status: RpcStatus, value: &[u8]) {
+    buf.put_u8(status as u8);
+    buf.put_u32_le(value.len() as u32);
+    buf.put_slice(value);
+}
+
+// Appends a value's CRC32C to it as a little-endian trailer, producing the
+// bytes actually stored in the table.
+fn with_checksum(value: &[u8], checksum: u32) -> Bytes {
+    let mut stored = BytesMut::with_capacity(value.len() + CHECKSUM_LEN);
+    stored.put_slice(value);
+    stored.put_u32_le(checksum);
+    stored.freeze()
+}
+
 struct User {
     // TODO(stutsman) Need some form of interior mutability here.
     id: UserId,
@@ -39,9 +73,11 @@ impl User {
         value.put_slice(&[91; 100]);
         let mut value: Bytes = value.freeze();
 
-        // Populate the table with this key-value pair.
+        // Populate the table with this key-value pair, storing a trailing
+        // CRC32C over the value so corruption can be detected on read.
         let key: Bytes = value.split_to(30);
-        table.put(key, value);
+        let checksum = crc32c(&value);
+        table.put(key, with_checksum(&value, checksum));
         self.tables.insert(table_id, table);
     }
 }
@@ -50,6 +86,23 @@ pub struct Master {
     // TODO(stutsman) Need some form of interior mutability here.
     users: HashMap<UserId, User>,
     extensions: ExtensionManager,
+
+    // The id of this physical node. Used to decide whether a key maps locally.
+    local_id: NodeId,
+
+    // The consistent-hashing ring used to place keys across the cluster. Held
+    // behind an `ArcSwap` so membership changes can atomically replace it
+    // without locking the request path.
+    ring: ArcSwap<Ring>,
+
+    // The cluster view: the active peer set discovered from Consul and
+    // persisted to disk. Guarded so discovery can mutate it from a background
+    // task while requests read the ring lock-free.
+    system: Mutex<System>,
+
+    // Per-opcode latency histograms over the hot RPC path, giving operators
+    // tail-latency visibility without an external profiler.
+    metrics: Metrics,
 }
 
 impl Master {
@@ -57,13 +110,41 @@ impl Master {
         let mut user = User::new(1);
         user.create_table(1);
 
+        // Load any persisted peer set and build the ring from it. A node that
+        // has never joined a cluster starts with just itself.
+        let local_id: NodeId = 1;
+        let mut system = System::new(PEERS_PATH);
+        if system.known_nodes().is_empty() {
+            system.merge(vec![super::membership::Peer {
+                id: local_id,
+                capacity: 256,
+                address: String::new(),
+            }]);
+        }
+        let ring = Ring::new(&system.node_configs(), REPLICATION_FACTOR);
+
         let mut master = Master{
             users: HashMap::new(),
             extensions: ExtensionManager::new(),
+            local_id,
+            ring: ArcSwap::from(Arc::new(ring)),
+            system: Mutex::new(system),
+            metrics: Metrics::new(),
         };
 
         master.users.insert(user.id, user);
 
+        // If a Consul catalog endpoint is configured, discover the cluster's
+        // healthy instances at startup and rebuild the ring from them. Both the
+        // endpoint and service name come from the environment so a node can
+        // bootstrap from Consul; absent them it relies solely on its persisted
+        // peer set.
+        if let Ok(endpoint) = std::env::var("CONSUL_HTTP_ADDR") {
+            let service = std::env::var("KAYAK_CONSUL_SERVICE")
+                .unwrap_or_else(|_| "kayak".to_string());
+            master.refresh_membership(&endpoint, &service);
+        }
+
         // Load a get extension for this user.
         master.extensions.load("../ext/get/target/release/libget.so", 1, "get")
                             .unwrap();
@@ -71,6 +152,134 @@ impl Master {
         master
     }
 
+    /// Returns a snapshot of per-opcode service-time percentiles and request
+    /// counts measured on the dispatch hot path.
+    pub fn latency_snapshot(&self) -> Vec<OpSnapshot> {
+        self.metrics.snapshot()
+    }
+
+    /// Returns the ids of every node this `Master` currently knows about. This
+    /// is the cluster view the ring is built from and the one routing
+    /// decisions in `dispatch` are made against.
+    pub fn known_nodes(&self) -> Vec<NodeId> {
+        self.system.lock().unwrap().known_nodes()
+    }
+
+    /// Discovers peers from the given Consul catalog endpoint, merges them into
+    /// the active set, and — if the set changed — persists it and atomically
+    /// rebuilds the ring so routing stays consistent across the cluster.
+    pub fn refresh_membership(&self, catalog_endpoint: &str, service: &str) {
+        let discovered = match discover_consul(catalog_endpoint, service) {
+            Ok(peers) => peers,
+            Err(e) => {
+                error!("Consul discovery failed: {}", e);
+                return;
+            }
+        };
+
+        let mut system = self.system.lock().unwrap();
+        if !system.merge(discovered) {
+            return;
+        }
+
+        if let Err(e) = system.persist() {
+            error!("Could not persist peer set: {}", e);
+        }
+
+        let ring = Ring::new(&system.node_configs(), REPLICATION_FACTOR);
+        self.ring.store(Arc::new(ring));
+    }
+
+    // Pushes a minimal common response header onto a raw UDP packet and sets
+    // the status matching the given error category, returning the packet
+    // deparsed back to its UDP header so it can be handed to ServerDispatch.
+    // Only the `InvalidOperation` arm of `dispatch` routes through here; the
+    // other arms push their own response header before handling the request
+    // and set any failure status on that header directly.
+    fn error_response(&self, respons: Packet<UdpHeader, EmptyMetadata>,
+                      category: ErrorCategory) -> Packet<UdpHeader, EmptyMetadata> {
+        let mut response_header = CommonResponse::new();
+        response_header.common_header.status = category.status();
+
+        let respons: Packet<CommonResponse, EmptyMetadata> =
+            respons.push_header(&response_header)
+                .expect("ERROR: Failed to setup common error response header");
+
+        respons.deparse_header(PACKET_UDP_LEN as usize)
+    }
+
+    // Returns true if a key belonging to `tenant_id`/`table_id` maps to this
+    // node under the current ring, and should therefore be serviced locally
+    // rather than forwarded to a peer.
+    fn serves_locally(&self, tenant_id: UserId, table_id: TableId, key: &[u8]) -> bool {
+        let ring = self.ring.load();
+        let partition = ring.partition_of(tenant_id, table_id, key);
+        ring.nodes_for(partition).contains(&self.local_id)
+    }
+
+    // Returns the set of nodes responsible for a key under the current ring.
+    fn replicas_for(&self, tenant_id: UserId, table_id: TableId, key: &[u8]) -> Vec<NodeId> {
+        let ring = self.ring.load();
+        let partition = ring.partition_of(tenant_id, table_id, key);
+        ring.nodes_for(partition)
+    }
+
+    // Reports whether a read quorum agreed on a key over the replicas
+    // responsible for it. Only the local replica is read, from the in-process
+    // store; remote replicas are counted in the total but are not contacted.
+    fn read_quorum(&self, tenant_id: UserId, table_id: TableId, key: &[u8]) -> Quorum {
+        let replicas = self.replicas_for(tenant_id, table_id, key);
+
+        // Only the local replica can be read today; the RPC forwarding path
+        // that would contact remote replicas does not exist yet, so `got`
+        // counts just the local ack. The quorum is still sized over the full
+        // replica set so the response reports the real observed/required/total
+        // counts — hiding the uncontacted replicas behind `total == 1` would
+        // make `StatusQuorumUnavailable` unreachable and mask a genuinely
+        // under-replicated read.
+        let mut got: usize = 0;
+        for node in &replicas {
+            if *node == self.local_id {
+                let present = self.users.get(&tenant_id)
+                    .and_then(|user| user.tables.get(&table_id))
+                    .and_then(|table| table.get(key))
+                    .is_some();
+                if present {
+                    got += 1;
+                }
+            }
+        }
+
+        Quorum::new(replicas.len(), got)
+    }
+
+    // Reports whether a write quorum acknowledged a value over the replicas
+    // responsible for a key. The value is applied to the local store only;
+    // remote replicas are counted in the total but are not sent the value.
+    fn write_quorum(&self, tenant_id: UserId, table_id: TableId,
+                    key: Bytes, value: Bytes) -> Quorum {
+        let replicas = self.replicas_for(tenant_id, table_id, key.as_ref());
+
+        // As in `read_quorum`, only the local replica is written today so `got`
+        // counts just its ack, but the quorum is sized over the full replica
+        // set (`replicas.len()`) so the response surfaces the real
+        // observed/required/total counts rather than pretending the key was
+        // singly replicated.
+        let mut got: usize = 0;
+        for node in &replicas {
+            if *node == self.local_id {
+                if let Some(user) = self.users.get(&tenant_id) {
+                    if let Some(table) = user.tables.get(&table_id) {
+                        table.put(key.clone(), value.clone());
+                        got += 1;
+                    }
+                }
+            }
+        }
+
+        Quorum::new(replicas.len(), got)
+    }
+
     // This method handles the Get() RPC request. A hash table lookup is
     // performed on a supplied tenant id, table id, and key. If successfull,
     // the result of the lookup is written into a response packet, and the
@@ -93,15 +302,28 @@ impl Master {
         // If the payload size is less than the key length, return an error.
         if request.get_payload().len() < key_length as usize {
             let resp_hdr: &mut GetResponse = respons.get_mut_header();
-            resp_hdr.common_header.status = RpcStatus::StatusMalformedRequest;
+            resp_hdr.common_header.status = ErrorCategory::Malformed.status();
             return;
         }
 
         // Get a reference to the key.
         let (key, _) = request.get_payload().split_at(key_length as usize);
 
+        // Consult the ring to decide whether this node owns the key. If it does
+        // not, the RPC must be forwarded to one of the responsible peers rather
+        // than serviced against the local store.
+        if !self.serves_locally(tenant_id, table_id, key) {
+            // TODO(stutsman) Forward the request to a node returned by
+            // `ring.nodes_for()`. Until the forwarding path exists the local
+            // store is the only replica, so fall through and serve locally.
+        }
+
         let mut status: RpcStatus = RpcStatus::StatusOk;
 
+        // The CRC32C of the value served, surfaced in the response header so the
+        // client can validate the bytes end-to-end.
+        let mut value_checksum: u32 = 0;
+
         let outcome =
                 // Check if the tenant exists.
             self.users.get(&tenant_id)
@@ -118,25 +340,53 @@ impl Master {
                                 status = RpcStatus::StatusTableDoesNotExist;
                                 None
                              }, | table | { table.get(key) })
-                // If the lookup succeeded, write the value to the
-                // response payload. If it didn't, update the status to reflect
-                // that.
+                // If the lookup succeeded, verify the value's trailing checksum
+                // and write the value (without the trailer) to the response
+                // payload. If the lookup failed or the checksum did not match,
+                // update the status to reflect that.
                 .map_or_else(|| {
                                 status = RpcStatus::StatusObjectDoesNotExist;
                                 None
                              }, | value | {
-                                 respons.add_to_payload_tail(value.len(),
-                                                            &value)
+                                 if value.len() < CHECKSUM_LEN {
+                                     status = RpcStatus::StatusChecksumMismatch;
+                                     return None;
+                                 }
+                                 let (data, trailer) =
+                                     value.split_at(value.len() - CHECKSUM_LEN);
+                                 let computed = crc32c(data);
+                                 if computed != u32::from_le_bytes(trailer.try_into().unwrap()) {
+                                     error!("Checksum mismatch on read.");
+                                     status = RpcStatus::StatusChecksumMismatch;
+                                     return None;
+                                 }
+                                 value_checksum = computed;
+                                 respons.add_to_payload_tail(data.len(), data)
                                         .ok()
                              })
                 // If the value could not be written to the response payload,
-                // update the status to reflect that.
+                // update the status to reflect that. A `None` here may instead
+                // carry a status set earlier in the chain — a missing
+                // tenant/table/object or a checksum mismatch — so only overwrite
+                // it with an internal error when nothing upstream already
+                // explained the failure.
                 .map_or_else(|| {
-                                status = RpcStatus::StatusInternalError;
-                                error!("Could not write to response payload.");
+                                if status == RpcStatus::StatusOk {
+                                    status = RpcStatus::StatusInternalError;
+                                    error!("Could not write to response payload.");
+                                }
                                 None
                              }, | _ | { Some(()) });
 
+        // Require a read quorum over the key's replica set, of which only the
+        // local replica is read (see `read_quorum`). A transient shortfall is reported as `StatusQuorumUnavailable` with
+        // the observed/required/total counts so the client can distinguish it
+        // from a permanent failure.
+        let quorum = self.read_quorum(tenant_id, table_id, key);
+        if status == RpcStatus::StatusOk && !quorum.satisfied() {
+            status = RpcStatus::StatusQuorumUnavailable;
+        }
+
         match outcome {
             // The RPC completed successfully. Update the response header with
             // the status and value length.
@@ -145,16 +395,203 @@ impl Master {
 
                 let resp_hdr: &mut GetResponse = respons.get_mut_header();
                 resp_hdr.value_length = val_len;
+                resp_hdr.checksum = value_checksum;
+                resp_hdr.checksum_algorithm = ALGORITHM_CRC32C;
                 resp_hdr.common_header.status = status;
+                resp_hdr.common_header.quorum_need = quorum.need;
+                resp_hdr.common_header.quorum_got = quorum.got;
+                resp_hdr.common_header.quorum_total = quorum.total;
             }
 
             // The RPC failed. Update the response header with the status.
             None => {
                 let resp_hdr: &mut GetResponse = respons.get_mut_header();
                 resp_hdr.common_header.status = status;
+                resp_hdr.common_header.quorum_need = quorum.need;
+                resp_hdr.common_header.quorum_got = quorum.got;
+                resp_hdr.common_header.quorum_total = quorum.total;
+            }
+        }
+
+        return;
+    }
+
+    // This method handles the Put() RPC request. The supplied value is written
+    // to the local replica only; the quorum is sized over the
+    // `replication_factor` nodes the ring assigns to the key, and success is
+    // reported only once a write quorum of `(N/2)+1` replicas acknowledges.
+    // A shortfall is reported as
+    // `StatusQuorumUnavailable` with the observed/required/total counts.
+    //
+    // # Arguments
+    //
+    // * `req_hdr`: A reference to the request header of the RPC.
+    // * `request`: A reference to the entire request packet.
+    // * `respons`: A mutable reference to the entire response packet.
+    fn put(&self, req_hdr: &PutRequest,
+           request: &Packet<PutRequest, EmptyMetadata>,
+           respons: &mut Packet<PutResponse, EmptyMetadata>) {
+        // Read fields of the request header.
+        let tenant_id: UserId = req_hdr.common_header.tenant as UserId;
+        let table_id: TableId = req_hdr.table_id as TableId;
+        let key_length: usize = req_hdr.key_length as usize;
+        let value_length: usize = req_hdr.value_length as usize;
+
+        // If the payload is shorter than the key and value, return an error.
+        if request.get_payload().len() < key_length + value_length {
+            let resp_hdr: &mut PutResponse = respons.get_mut_header();
+            resp_hdr.common_header.status = ErrorCategory::Malformed.status();
+            return;
+        }
+
+        // Split the payload into the key and the value to be stored.
+        let (key, rest) = request.get_payload().split_at(key_length);
+        let (value, _) = rest.split_at(value_length);
+        let key: Bytes = Bytes::from(key.to_vec());
+        let value: Bytes = Bytes::from(value.to_vec());
+
+        // The tenant must exist before anything is written.
+        let user = match self.users.get(&tenant_id) {
+            Some(user) => user,
+            None => {
+                let resp_hdr: &mut PutResponse = respons.get_mut_header();
+                resp_hdr.common_header.status = ErrorCategory::Unauthorized.status();
+                return;
             }
+        };
+
+        // The target table must exist too. A write to a missing table is a
+        // permanent client error and is reported as such, rather than being
+        // left to fall through `write_quorum` with no ack and be misreported as
+        // a transient `StatusQuorumUnavailable`.
+        if user.tables.get(&table_id).is_none() {
+            let resp_hdr: &mut PutResponse = respons.get_mut_header();
+            resp_hdr.common_header.status = RpcStatus::StatusTableDoesNotExist;
+            return;
+        }
+
+        // Compute a CRC32C over the value. If the client supplied its own
+        // checksum, verify it so a value corrupted in transit is rejected
+        // before it is stored; otherwise the server-computed checksum is kept.
+        let checksum = crc32c(&value);
+        if req_hdr.checksum_algorithm == ALGORITHM_CRC32C
+            && req_hdr.checksum != checksum {
+            error!("Checksum mismatch on write.");
+            let resp_hdr: &mut PutResponse = respons.get_mut_header();
+            resp_hdr.common_header.status = RpcStatus::StatusChecksumMismatch;
+            return;
         }
 
+        // Store the value with its checksum appended as a trailer.
+        let stored = with_checksum(&value, checksum);
+
+        // Replicate the value and require a write quorum to acknowledge.
+        let quorum = self.write_quorum(tenant_id, table_id, key, stored);
+        let status = if quorum.satisfied() {
+            RpcStatus::StatusOk
+        } else {
+            RpcStatus::StatusQuorumUnavailable
+        };
+
+        let resp_hdr: &mut PutResponse = respons.get_mut_header();
+        resp_hdr.common_header.status = status;
+        resp_hdr.common_header.quorum_need = quorum.need;
+        resp_hdr.common_header.quorum_got = quorum.got;
+        resp_hdr.common_header.quorum_total = quorum.total;
+
+        return;
+    }
+
+    // This method handles the MultiGet() RPC request. The request payload is a
+    // count-prefixed list of `(table_id, key_length, key)` tuples; each key is
+    // looked up independently and a length-delimited result is written to the
+    // response, tagged with its own `RpcStatus` so a single missing object does
+    // not fail the whole batch.
+    //
+    // # Arguments
+    //
+    // * `req_hdr`: A reference to the request header of the RPC.
+    // * `request`: A reference to the entire request packet.
+    // * `respons`: A mutable reference to the entire response packet.
+    fn multi_get(&self, req_hdr: &MultiGetRequest,
+                 request: &Packet<MultiGetRequest, EmptyMetadata>,
+                 respons: &mut Packet<MultiGetResponse, EmptyMetadata>) {
+        let tenant_id: UserId = req_hdr.common_header.tenant as UserId;
+        let num_keys: u32 = req_hdr.num_keys;
+
+        // Each result is serialized as a one-byte status, a four-byte value
+        // length, and the value bytes, so the client can walk the sequence
+        // without a separate index.
+        let mut results = BytesMut::new();
+
+        // Walk the count-prefixed tuple list. A tuple that runs past the end of
+        // the payload aborts the batch with a malformed-request status, since
+        // the remaining offsets can no longer be trusted.
+        let mut payload = request.get_payload();
+        let mut malformed = false;
+        for _ in 0..num_keys {
+            if payload.len() < 10 {
+                malformed = true;
+                break;
+            }
+            let (table_bytes, rest) = payload.split_at(8);
+            let (len_bytes, rest) = rest.split_at(2);
+            let table_id: TableId =
+                u64::from_le_bytes(table_bytes.try_into().unwrap()) as TableId;
+            let key_length =
+                u16::from_le_bytes(len_bytes.try_into().unwrap()) as usize;
+
+            if rest.len() < key_length {
+                malformed = true;
+                break;
+            }
+            let (key, rest) = rest.split_at(key_length);
+            payload = rest;
+
+            let value = self.users.get(&tenant_id)
+                .and_then(|user| user.tables.get(&table_id))
+                .and_then(|table| table.get(key));
+
+            // Each stored value carries a trailing CRC32C; strip and verify it
+            // exactly as `get` does so a batched read returns the value without
+            // its trailer and surfaces `StatusChecksumMismatch` per key rather
+            // than handing the client four garbage bytes unchecked.
+            match value {
+                Some(value) => {
+                    if value.len() < CHECKSUM_LEN {
+                        push_result(&mut results, RpcStatus::StatusChecksumMismatch, &[]);
+                        continue;
+                    }
+                    let (data, trailer) = value.split_at(value.len() - CHECKSUM_LEN);
+                    if crc32c(data) != u32::from_le_bytes(trailer.try_into().unwrap()) {
+                        error!("Checksum mismatch on multi-get read.");
+                        push_result(&mut results, RpcStatus::StatusChecksumMismatch, &[]);
+                        continue;
+                    }
+                    push_result(&mut results, RpcStatus::StatusOk, data);
+                }
+                None => push_result(&mut results, RpcStatus::StatusObjectDoesNotExist, &[]),
+            }
+        }
+
+        if malformed {
+            let resp_hdr: &mut MultiGetResponse = respons.get_mut_header();
+            resp_hdr.common_header.status = ErrorCategory::Malformed.status();
+            return;
+        }
+
+        let results = results.freeze();
+        if respons.add_to_payload_tail(results.len(), &results).is_err() {
+            error!("Could not write to multi-get response payload.");
+            let resp_hdr: &mut MultiGetResponse = respons.get_mut_header();
+            resp_hdr.common_header.status = RpcStatus::StatusInternalError;
+            return;
+        }
+
+        let resp_hdr: &mut MultiGetResponse = respons.get_mut_header();
+        resp_hdr.num_keys = num_keys;
+        resp_hdr.common_header.status = RpcStatus::StatusOk;
+
         return;
     }
 
@@ -170,7 +607,7 @@ impl Master {
         // length, return an error.
         if request.get_payload().len() < name_length + args_length {
             let resp_hdr: &mut InvokeResponse = respons.get_mut_header();
-            resp_hdr.common_header.status = RpcStatus::StatusMalformedRequest;
+            resp_hdr.common_header.status = ErrorCategory::Malformed.status();
             return;
         }
 
@@ -188,7 +625,7 @@ impl Master {
             None => {
                 let resp_hdr: &mut InvokeResponse = respons.get_mut_header();
                 resp_hdr.common_header.status =
-                                            RpcStatus::StatusTenantDoesNotExist;
+                                            ErrorCategory::Unauthorized.status();
                 return;
             }
         }
@@ -235,8 +672,58 @@ impl Service for Master {
                     respons.push_header(&response_header)
                         .expect("ERROR: Failed to setup Get() response header");
 
+                // Handle the RPC request, timing its service on the hot path.
+                self.metrics.time(OpCode::SandstormGetRpc, || {
+                    self.get(request.get_header(), &request, &mut respons);
+                });
+
+                // Deparse request and response headers so that packets can
+                // be handed back to ServerDispatch.
+                let request: Packet<UdpHeader, EmptyMetadata> =
+                    request.deparse_header(PACKET_UDP_LEN as usize);
+                let respons: Packet<UdpHeader, EmptyMetadata> =
+                    respons.deparse_header(PACKET_UDP_LEN as usize);
+
+                return (request, respons);
+            }
+
+            OpCode::SandstormPutRpc => {
+                let request: Packet<PutRequest, EmptyMetadata> =
+                    request.parse_header::<PutRequest>();
+
+                // Create a response header for the request.
+                let response_header = PutResponse::new();
+                let mut respons: Packet<PutResponse, EmptyMetadata> =
+                    respons.push_header(&response_header)
+                        .expect("ERROR: Failed to setup Put() response header");
+
                 // Handle the RPC request.
-                self.get(request.get_header(), &request, &mut respons);
+                self.put(request.get_header(), &request, &mut respons);
+
+                // Deparse request and response headers so that packets can
+                // be handed back to ServerDispatch.
+                let request: Packet<UdpHeader, EmptyMetadata> =
+                    request.deparse_header(PACKET_UDP_LEN as usize);
+                let respons: Packet<UdpHeader, EmptyMetadata> =
+                    respons.deparse_header(PACKET_UDP_LEN as usize);
+
+                return (request, respons);
+            }
+
+            OpCode::SandstormMultiGetRpc => {
+                let request: Packet<MultiGetRequest, EmptyMetadata> =
+                    request.parse_header::<MultiGetRequest>();
+
+                // Create a response header for the request.
+                let response_header = MultiGetResponse::new();
+                let mut respons: Packet<MultiGetResponse, EmptyMetadata> =
+                    respons.push_header(&response_header)
+                        .expect("ERROR: Failed to setup MultiGet() resp header");
+
+                // Handle the RPC request, timing its service on the hot path.
+                self.metrics.time(OpCode::SandstormMultiGetRpc, || {
+                    self.multi_get(request.get_header(), &request, &mut respons);
+                });
 
                 // Deparse request and response headers so that packets can
                 // be handed back to ServerDispatch.
@@ -258,8 +745,10 @@ impl Service for Master {
                     respons.push_header(&response_header)
                         .expect("ERROR: Failed to setup invoke() resp header");
 
-                // Handle the RPC request.
-                self.invoke(request.get_header(), &request, &mut respons);
+                // Handle the RPC request, timing its service on the hot path.
+                self.metrics.time(OpCode::SandstormInvokeRpc, || {
+                    self.invoke(request.get_header(), &request, &mut respons);
+                });
 
                 // Deparse request and response headers so that packets can
                 // be handed back to ServerDispatch.
@@ -272,9 +761,12 @@ impl Service for Master {
             }
 
             OpCode::InvalidOperation => {
-                // TODO: Set error message on the response packet,
-                // deparse respons to UDP header. At present, the
-                // response packet will have an empty response header.
+                // The request carried an opcode this service does not
+                // implement. Return a well-formed, status-bearing response
+                // rather than a packet with an uninitialized header.
+                let respons = self.metrics.time(OpCode::InvalidOperation, || {
+                    self.error_response(respons, ErrorCategory::UnknownOpcode)
+                });
                 return (request, respons);
             }
         }
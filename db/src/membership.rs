@@ -0,0 +1,498 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::time::Duration;
+
+use super::ring::{NodeConfig, NodeId};
+
+/// A peer node known to this `Master`. `address` is the host:port the node
+/// advertises for RPC; `capacity` is the weight it carries on the ring.
+#[derive(Clone)]
+pub struct Peer {
+    pub id: NodeId,
+    pub capacity: u16,
+    pub address: String,
+}
+
+/// Tracks the active peer set of a `Master` node and knows how to discover it
+/// from a Consul service catalog and how to persist it to disk, mirroring the
+/// discovery-plus-persistence approach used by other distributed stores. The
+/// persisted file lets a full-cluster restart bootstrap the ring without
+/// Consul being reachable.
+pub struct Membership {
+    // Peers keyed by node id so the set stays deduplicated and ordered.
+    peers: BTreeMap<NodeId, Peer>,
+    // Path the active peer set is persisted to.
+    path: String,
+}
+
+impl Membership {
+    /// Creates a membership view backed by the file at `path`, loading any
+    /// previously persisted peer set. A missing file is not an error: the node
+    /// simply starts with an empty set and populates it from discovery.
+    pub fn new(path: &str) -> Membership {
+        let mut membership = Membership {
+            peers: BTreeMap::new(),
+            path: path.to_string(),
+        };
+
+        if let Ok(peers) = Membership::load(path) {
+            for peer in peers {
+                membership.peers.insert(peer.id, peer);
+            }
+        }
+
+        membership
+    }
+
+    /// Merges a set of discovered peers into the active set. Returns true if the
+    /// set changed, in which case the caller should persist and rebuild the
+    /// ring.
+    pub fn merge(&mut self, discovered: Vec<Peer>) -> bool {
+        let mut changed = false;
+        for peer in discovered {
+            let differs = self.peers.get(&peer.id).map_or(true, |p| {
+                p.capacity != peer.capacity || p.address != peer.address
+            });
+            if differs {
+                self.peers.insert(peer.id, peer);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Returns the ids of every known node, ordered, for the `System` view.
+    pub fn known_nodes(&self) -> Vec<NodeId> {
+        self.peers.keys().cloned().collect()
+    }
+
+    /// Builds the ring's node configuration from the active peer set.
+    pub fn node_configs(&self) -> Vec<NodeConfig> {
+        self.peers
+            .values()
+            .map(|p| NodeConfig { id: p.id, capacity: p.capacity })
+            .collect()
+    }
+
+    /// Atomically persists the active peer set back to the configured file so a
+    /// later restart can bootstrap from it without Consul. Written to a
+    /// temporary file and renamed so a crash mid-write cannot truncate the
+    /// existing set.
+    pub fn persist(&self) -> Result<()> {
+        let mut body = String::new();
+        for peer in self.peers.values() {
+            body.push_str(&format!("{} {} {}\n", peer.id, peer.capacity, peer.address));
+        }
+
+        let tmp = format!("{}.tmp", self.path);
+        fs::write(&tmp, body)?;
+        fs::rename(&tmp, &self.path)
+    }
+
+    // Loads a persisted peer set from disk, parsing the `id capacity address`
+    // line format written by `persist`.
+    fn load(path: &str) -> Result<Vec<Peer>> {
+        if !Path::new(path).exists() {
+            return Ok(Vec::new());
+        }
+
+        let body = fs::read_to_string(path)?;
+        let mut peers = Vec::new();
+        for line in body.lines() {
+            let mut fields = line.split_whitespace();
+            let id = fields.next().and_then(|f| f.parse::<NodeId>().ok());
+            let capacity = fields.next().and_then(|f| f.parse::<u16>().ok());
+            let address = fields.next().map(|f| f.to_string());
+            match (id, capacity, address) {
+                (Some(id), Some(capacity), Some(address)) => {
+                    peers.push(Peer { id, capacity, address });
+                }
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "malformed persisted peer entry",
+                    ));
+                }
+            }
+        }
+        Ok(peers)
+    }
+}
+
+/// The cluster view of a `Master` node: the set of peers it knows about and
+/// the ring configuration derived from them. `System` is the surface the rest
+/// of the node queries for cluster membership; it owns the `Membership` store
+/// that discovers peers and persists them to disk.
+pub struct System {
+    membership: Membership,
+}
+
+impl System {
+    /// Builds the cluster view backed by the peer file at `path`, loading any
+    /// previously persisted peer set.
+    pub fn new(path: &str) -> System {
+        System { membership: Membership::new(path) }
+    }
+
+    /// Returns the ids of every node currently in the cluster view. This is the
+    /// node set the ring is built from and that routing decisions are made
+    /// against.
+    pub fn known_nodes(&self) -> Vec<NodeId> {
+        self.membership.known_nodes()
+    }
+
+    /// Builds the ring's node configuration from the current cluster view.
+    pub fn node_configs(&self) -> Vec<NodeConfig> {
+        self.membership.node_configs()
+    }
+
+    /// Merges discovered peers into the cluster view, returning true if it
+    /// changed.
+    pub fn merge(&mut self, discovered: Vec<Peer>) -> bool {
+        self.membership.merge(discovered)
+    }
+
+    /// Persists the current cluster view so a restart can bootstrap from it.
+    pub fn persist(&self) -> Result<()> {
+        self.membership.persist()
+    }
+}
+
+// How long to wait on the Consul catalog socket before giving up. Discovery is
+// best-effort: a timeout surfaces as an error the caller logs and skips,
+// falling back to the persisted peer set.
+const CONSUL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Queries a Consul service catalog endpoint for the healthy instances of a
+/// service and returns them as peers. The `/v1/health/service/<service>?passing`
+/// array is fetched over HTTP and each entry that carries a `node_id` in its
+/// service metadata contributes one peer built from that id, the `capacity`
+/// metadata and the advertised `address:port`; entries without one are skipped.
+///
+/// The crate has no HTTP client dependency, so the request is issued directly
+/// over a `TcpStream`; `catalog_endpoint` is the `host:port` of the agent's HTTP
+/// API (e.g. `127.0.0.1:8500`).
+pub fn discover_consul(catalog_endpoint: &str, service: &str) -> Result<Vec<Peer>> {
+    let path = format!("/v1/health/service/{}?passing", service);
+    let body = http_get(catalog_endpoint, &path)?;
+
+    let json = Json::parse(&body)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "malformed Consul response"))?;
+
+    let entries = json.as_array()
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Consul response was not an array"))?;
+
+    let mut peers = Vec::new();
+    for entry in entries {
+        let node = entry.get("Node");
+        let svc = entry.get("Service");
+        let meta = svc.and_then(|s| s.get("Meta"));
+
+        // A node id is required to place the peer on the ring; an instance that
+        // does not advertise one is skipped rather than failing the whole
+        // discovery pass.
+        let id = match meta.and_then(|m| m.get("node_id")).and_then(Json::as_u64) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        let capacity = meta
+            .and_then(|m| m.get("capacity"))
+            .and_then(Json::as_u64)
+            .map(|c| c as u16)
+            .unwrap_or(256);
+
+        let host = svc
+            .and_then(|s| s.get("Address"))
+            .and_then(Json::as_str)
+            .filter(|s| !s.is_empty())
+            .or_else(|| node.and_then(|n| n.get("Address")).and_then(Json::as_str))
+            .unwrap_or("")
+            .to_string();
+        let port = svc.and_then(|s| s.get("Port")).and_then(Json::as_u64).unwrap_or(0);
+        let address = format!("{}:{}", host, port);
+
+        peers.push(Peer { id, capacity, address });
+    }
+
+    Ok(peers)
+}
+
+// Issues a minimal HTTP/1.0 GET against `host:port` and returns the response
+// body. Connection is closed after the response so the whole body can be read
+// to EOF without parsing a content length.
+fn http_get(endpoint: &str, path: &str) -> Result<String> {
+    let mut stream = TcpStream::connect(endpoint)?;
+    stream.set_read_timeout(Some(CONSUL_TIMEOUT))?;
+    stream.set_write_timeout(Some(CONSUL_TIMEOUT))?;
+
+    let request = format!(
+        "GET {} HTTP/1.0\r\nHost: {}\r\nAccept: application/json\r\nConnection: close\r\n\r\n",
+        path, endpoint,
+    );
+    stream.write_all(request.as_bytes())?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    // Split the status line and headers from the body at the blank line.
+    match response.find("\r\n\r\n") {
+        Some(split) => Ok(response[split + 4..].to_string()),
+        None => Err(Error::new(ErrorKind::InvalidData, "Consul response had no body")),
+    }
+}
+
+// A minimal JSON value, sufficient to walk the subset of the Consul health
+// response the ring needs. A hand-rolled parser is carried rather than a
+// dependency because the crate has no JSON client wired in.
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(BTreeMap<String, Json>),
+}
+
+impl Json {
+    // Parses a complete JSON document, returning None on any malformed input.
+    fn parse(input: &str) -> Option<Json> {
+        let bytes = input.as_bytes();
+        let mut pos = 0;
+        let value = parse_value(bytes, &mut pos)?;
+        skip_whitespace(bytes, &mut pos);
+        if pos == bytes.len() {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    // Returns the named field of an object, or None for any other value.
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    // Interprets a JSON number or a numeric string as a u64, so a capacity or
+    // node id carried either way in Consul metadata is accepted.
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            Json::Number(n) => Some(*n as u64),
+            Json::String(s) => s.parse::<u64>().ok(),
+            _ => None,
+        }
+    }
+}
+
+fn skip_whitespace(bytes: &[u8], pos: &mut usize) {
+    while *pos < bytes.len() && matches!(bytes[*pos], b' ' | b'\t' | b'\n' | b'\r') {
+        *pos += 1;
+    }
+}
+
+fn parse_value(bytes: &[u8], pos: &mut usize) -> Option<Json> {
+    skip_whitespace(bytes, pos);
+    match bytes.get(*pos)? {
+        b'{' => parse_object(bytes, pos),
+        b'[' => parse_array(bytes, pos),
+        b'"' => parse_string(bytes, pos).map(Json::String),
+        b't' | b'f' => parse_bool(bytes, pos),
+        b'n' => parse_null(bytes, pos),
+        _ => parse_number(bytes, pos),
+    }
+}
+
+fn parse_object(bytes: &[u8], pos: &mut usize) -> Option<Json> {
+    *pos += 1; // consume '{'
+    let mut map = BTreeMap::new();
+    skip_whitespace(bytes, pos);
+    if bytes.get(*pos) == Some(&b'}') {
+        *pos += 1;
+        return Some(Json::Object(map));
+    }
+    loop {
+        skip_whitespace(bytes, pos);
+        let key = parse_string(bytes, pos)?;
+        skip_whitespace(bytes, pos);
+        if bytes.get(*pos) != Some(&b':') {
+            return None;
+        }
+        *pos += 1;
+        let value = parse_value(bytes, pos)?;
+        map.insert(key, value);
+        skip_whitespace(bytes, pos);
+        match bytes.get(*pos) {
+            Some(&b',') => *pos += 1,
+            Some(&b'}') => {
+                *pos += 1;
+                return Some(Json::Object(map));
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn parse_array(bytes: &[u8], pos: &mut usize) -> Option<Json> {
+    *pos += 1; // consume '['
+    let mut items = Vec::new();
+    skip_whitespace(bytes, pos);
+    if bytes.get(*pos) == Some(&b']') {
+        *pos += 1;
+        return Some(Json::Array(items));
+    }
+    loop {
+        let value = parse_value(bytes, pos)?;
+        items.push(value);
+        skip_whitespace(bytes, pos);
+        match bytes.get(*pos) {
+            Some(&b',') => *pos += 1,
+            Some(&b']') => {
+                *pos += 1;
+                return Some(Json::Array(items));
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn parse_string(bytes: &[u8], pos: &mut usize) -> Option<String> {
+    if bytes.get(*pos) != Some(&b'"') {
+        return None;
+    }
+    *pos += 1;
+    let mut out = String::new();
+    while let Some(&b) = bytes.get(*pos) {
+        *pos += 1;
+        match b {
+            b'"' => return Some(out),
+            b'\\' => {
+                let escaped = bytes.get(*pos)?;
+                *pos += 1;
+                match escaped {
+                    b'"' => out.push('"'),
+                    b'\\' => out.push('\\'),
+                    b'/' => out.push('/'),
+                    b'n' => out.push('\n'),
+                    b't' => out.push('\t'),
+                    b'r' => out.push('\r'),
+                    // Other escapes (including \u) are not needed by the fields
+                    // the ring reads, so the escaped byte is kept verbatim.
+                    other => out.push(*other as char),
+                }
+            }
+            _ => out.push(b as char),
+        }
+    }
+    None
+}
+
+fn parse_bool(bytes: &[u8], pos: &mut usize) -> Option<Json> {
+    if bytes[*pos..].starts_with(b"true") {
+        *pos += 4;
+        Some(Json::Bool(true))
+    } else if bytes[*pos..].starts_with(b"false") {
+        *pos += 5;
+        Some(Json::Bool(false))
+    } else {
+        None
+    }
+}
+
+fn parse_null(bytes: &[u8], pos: &mut usize) -> Option<Json> {
+    if bytes[*pos..].starts_with(b"null") {
+        *pos += 4;
+        Some(Json::Null)
+    } else {
+        None
+    }
+}
+
+fn parse_number(bytes: &[u8], pos: &mut usize) -> Option<Json> {
+    let start = *pos;
+    while let Some(&b) = bytes.get(*pos) {
+        if matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') {
+            *pos += 1;
+        } else {
+            break;
+        }
+    }
+    if *pos == start {
+        return None;
+    }
+    std::str::from_utf8(&bytes[start..*pos])
+        .ok()?
+        .parse::<f64>()
+        .ok()
+        .map(Json::Number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_consul_shape() {
+        let input = r#"[{"Node":{"Address":"10.0.0.1"},
+            "Service":{"Address":"10.0.0.2","Port":9000,
+            "Meta":{"node_id":"7","capacity":"128"}}}]"#;
+        let json = Json::parse(input).expect("valid JSON");
+        let entries = json.as_array().expect("array at top level");
+        assert_eq!(entries.len(), 1);
+
+        let svc = entries[0].get("Service").expect("Service object");
+        assert_eq!(svc.get("Port").and_then(Json::as_u64), Some(9000));
+
+        let meta = svc.get("Meta").expect("Meta object");
+        assert_eq!(meta.get("node_id").and_then(Json::as_u64), Some(7));
+        assert_eq!(meta.get("capacity").and_then(Json::as_u64), Some(128));
+    }
+
+    // Both a JSON number and a numeric string decode as a u64, since Consul
+    // carries metadata values as strings but other fields as bare numbers.
+    #[test]
+    fn number_and_numeric_string_both_decode() {
+        let json = Json::parse(r#"{"a":5,"b":"6"}"#).unwrap();
+        assert_eq!(json.get("a").and_then(Json::as_u64), Some(5));
+        assert_eq!(json.get("b").and_then(Json::as_u64), Some(6));
+    }
+
+    #[test]
+    fn decodes_string_escapes() {
+        let json = Json::parse(r#"{"k":"a\"b\n"}"#).unwrap();
+        assert_eq!(json.get("k").and_then(Json::as_str), Some("a\"b\n"));
+    }
+
+    #[test]
+    fn empty_array_and_object() {
+        assert_eq!(Json::parse("[]").unwrap().as_array().map(<[_]>::len), Some(0));
+        assert!(Json::parse("{}").unwrap().get("missing").is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(Json::parse("{").is_none());
+        assert!(Json::parse("[1,2").is_none());
+        assert!(Json::parse("{} trailing").is_none());
+        assert!(Json::parse("").is_none());
+    }
+}
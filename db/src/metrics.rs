@@ -0,0 +1,243 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::wireformat::OpCode;
+
+use e2d2::utils::asm::{cpuid, rdtsc_unsafe, rdtscp_unsafe};
+
+// The number of log-bucketed power-of-two ranges a histogram records into. A
+// 64-bit cycle count has at most 64 significant bits, so one bucket per bit
+// covers the whole range.
+const NUM_BUCKETS: usize = 64;
+
+// The opcodes service time is tracked for. Kept in a fixed array so a
+// histogram can be indexed by opcode without a map on the hot path.
+const TRACKED: [OpCode; 4] = [
+    OpCode::SandstormGetRpc,
+    OpCode::SandstormMultiGetRpc,
+    OpCode::SandstormInvokeRpc,
+    OpCode::InvalidOperation,
+];
+
+/// A lock-free HDR-style latency histogram. Samples are bucketed by the
+/// power-of-two range their cycle count falls in; alongside the buckets a
+/// running count, sum, and max are kept so a snapshot can report the count and
+/// the extreme without scanning. The sum is recorded but not reported.
+struct Histogram {
+    buckets: [AtomicU64; NUM_BUCKETS],
+    count: AtomicU64,
+    sum: AtomicU64,
+    max: AtomicU64,
+}
+
+impl Histogram {
+    fn new() -> Histogram {
+        Histogram {
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0),
+            max: AtomicU64::new(0),
+        }
+    }
+
+    // Records a single service-time sample, in cycles.
+    fn record(&self, cycles: u64) {
+        let bucket = bucket_of(cycles);
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(cycles, Ordering::Relaxed);
+        self.max.fetch_max(cycles, Ordering::Relaxed);
+    }
+
+    // Returns the lower bound of the bucket the q-th percentile falls in, in
+    // cycles. `q` is a fraction in `[0, 1]`.
+    fn percentile(&self, q: f64) -> u64 {
+        let count = self.count.load(Ordering::Relaxed);
+        if count == 0 {
+            return 0;
+        }
+
+        let target = (q * count as f64).ceil() as u64;
+        let mut cumulative = 0u64;
+        for (bucket, slot) in self.buckets.iter().enumerate() {
+            cumulative += slot.load(Ordering::Relaxed);
+            if cumulative >= target {
+                return bucket_lower_bound(bucket);
+            }
+        }
+        self.max.load(Ordering::Relaxed)
+    }
+}
+
+/// A snapshot of the service time recorded for a single opcode. Cycle figures
+/// are converted to nanoseconds using the TSC frequency calibrated at startup.
+pub struct OpSnapshot {
+    pub opcode: OpCode,
+    pub count: u64,
+    pub p50_ns: u64,
+    pub p99_ns: u64,
+    pub max_ns: u64,
+}
+
+/// Per-opcode latency metrics for the hot RPC path. The Get, MultiGet, Invoke
+/// and InvalidOperation branches of `Master::dispatch` are timed with a serializing `cpuid` + `rdtsc` read before
+/// the work and an `rdtscp` read after, and the elapsed cycles are accumulated
+/// into the opcode's histogram. The Put branch is not timed.
+pub struct Metrics {
+    histograms: [Histogram; TRACKED.len()],
+    // Calibrated TSC frequency in cycles per nanosecond.
+    cycles_per_ns: f64,
+}
+
+impl Metrics {
+    /// Builds a metrics subsystem, calibrating the TSC frequency once. The
+    /// calibration measures the cycle count across a fixed wall-clock sleep
+    /// interval so cycles can later be converted to nanoseconds.
+    pub fn new() -> Metrics {
+        Metrics {
+            histograms: std::array::from_fn(|_| Histogram::new()),
+            cycles_per_ns: calibrate_tsc(),
+        }
+    }
+
+    /// Times the execution of `work`, attributing the elapsed cycles to
+    /// `opcode`. A serializing `cpuid` + `rdtsc` read fences the start and an
+    /// `rdtscp` read fences the end so neighbouring instructions do not leak
+    /// into the measurement.
+    pub fn time<F, R>(&self, opcode: OpCode, work: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        cpuid();
+        let start = rdtsc_unsafe();
+
+        let result = work();
+
+        let end = rdtscp_unsafe();
+        if let Some(index) = index_of(opcode) {
+            self.histograms[index].record(end.wrapping_sub(start));
+        }
+
+        result
+    }
+
+    /// Returns a snapshot of p50/p99/max service time and request count for
+    /// every tracked opcode.
+    pub fn snapshot(&self) -> Vec<OpSnapshot> {
+        TRACKED
+            .iter()
+            .enumerate()
+            .map(|(index, opcode)| {
+                let h = &self.histograms[index];
+                OpSnapshot {
+                    opcode: *opcode,
+                    count: h.count.load(Ordering::Relaxed),
+                    p50_ns: self.to_ns(h.percentile(0.50)),
+                    p99_ns: self.to_ns(h.percentile(0.99)),
+                    max_ns: self.to_ns(h.max.load(Ordering::Relaxed)),
+                }
+            })
+            .collect()
+    }
+
+    // Converts a cycle count to nanoseconds using the calibrated frequency.
+    fn to_ns(&self, cycles: u64) -> u64 {
+        (cycles as f64 / self.cycles_per_ns) as u64
+    }
+}
+
+// Returns the index of an opcode's histogram in the tracked array.
+fn index_of(opcode: OpCode) -> Option<usize> {
+    TRACKED.iter().position(|o| *o == opcode)
+}
+
+// Returns the power-of-two bucket a cycle count falls in. Clamped to the last
+// bucket so a sample with bit 63 set (`leading_zeros() == 0`) cannot index past
+// the array: `time()` records `end.wrapping_sub(start)`, and TSC
+// non-monotonicity across cores can make `end < start`, producing a near-`2^64`
+// sample on the hot path.
+fn bucket_of(cycles: u64) -> usize {
+    let bucket = (NUM_BUCKETS as u32 - cycles.leading_zeros()) as usize;
+    bucket.min(NUM_BUCKETS - 1)
+}
+
+// Returns the lower bound, in cycles, of a bucket.
+fn bucket_lower_bound(bucket: usize) -> u64 {
+    if bucket == 0 {
+        0
+    } else {
+        1u64 << (bucket - 1)
+    }
+}
+
+// Measures the TSC frequency once, as cycles per nanosecond, by counting
+// cycles over a fixed wall-clock interval.
+fn calibrate_tsc() -> f64 {
+    let interval = Duration::from_millis(10);
+
+    cpuid();
+    let start = rdtsc_unsafe();
+    let wall_start = Instant::now();
+
+    thread::sleep(interval);
+
+    let end = rdtscp_unsafe();
+    let elapsed_ns = wall_start.elapsed().as_nanos() as f64;
+
+    let cycles = end.wrapping_sub(start) as f64;
+    if elapsed_ns > 0.0 {
+        cycles / elapsed_ns
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::Ordering;
+
+    // The bucket index must never exceed the last slot, including for the
+    // near-2^64 samples a non-monotonic TSC can produce.
+    #[test]
+    fn bucket_of_is_bounded() {
+        assert_eq!(bucket_of(0), 0);
+        assert_eq!(bucket_of(1), 1);
+        assert_eq!(bucket_of(1 << 10), 11);
+        // Bit 62 set has one leading zero, so it already lands in the last
+        // bucket, as does bit 63 and the all-ones sample.
+        assert_eq!(bucket_of(1 << 62), NUM_BUCKETS - 1);
+        assert_eq!(bucket_of(1 << 63), NUM_BUCKETS - 1);
+        assert_eq!(bucket_of(u64::max_value()), NUM_BUCKETS - 1);
+    }
+
+    // Recording an extreme sample must not panic on the hot path.
+    #[test]
+    fn record_handles_extreme_sample() {
+        let h = Histogram::new();
+        h.record(u64::max_value());
+        h.record(1000);
+        assert_eq!(h.count.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn percentile_is_monotonic_and_zero_when_empty() {
+        let h = Histogram::new();
+        assert_eq!(h.percentile(0.5), 0);
+
+        for cycles in &[10u64, 20, 40, 80, 160, 320] {
+            h.record(*cycles);
+        }
+        assert!(h.percentile(0.50) <= h.percentile(0.99));
+    }
+
+    #[test]
+    fn bucket_lower_bound_matches_bucket_of() {
+        // A sample sits at or above the lower bound of the bucket it falls in.
+        for sample in &[1u64, 2, 3, 255, 256, 1 << 20] {
+            let bucket = bucket_of(*sample);
+            assert!(*sample >= bucket_lower_bound(bucket));
+        }
+    }
+}
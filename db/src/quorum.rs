@@ -0,0 +1,34 @@
+/// Describes the outcome of a replicated read or write: how many replicas were
+/// required to form a quorum, how many actually acknowledged, and the total
+/// number the operation was sent to. Mirrors the `Quorum(need, got, total)`
+/// shape used by other distributed stores so clients can distinguish a
+/// transient degradation from a permanent failure.
+#[derive(Clone, Copy)]
+pub struct Quorum {
+    pub need: u16,
+    pub got: u16,
+    pub total: u16,
+}
+
+impl Quorum {
+    /// The number of acknowledgements required to satisfy a read or write
+    /// quorum over `total` replicas, i.e. `(N/2)+1`.
+    pub fn required(total: usize) -> u16 {
+        (total / 2 + 1) as u16
+    }
+
+    /// Builds a quorum result from the replica set and the acknowledgements
+    /// collected from it.
+    pub fn new(total: usize, got: usize) -> Quorum {
+        Quorum {
+            need: Quorum::required(total),
+            got: got as u16,
+            total: total as u16,
+        }
+    }
+
+    /// Returns true once at least `need` replicas have acknowledged.
+    pub fn satisfied(&self) -> bool {
+        self.got >= self.need
+    }
+}
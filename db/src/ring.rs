@@ -0,0 +1,192 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use super::common::{TableId, UserId};
+
+/// The number of partitions the key space is split into. Must be a power of
+/// two so that a partition index can be taken from the top bits of a key hash
+/// with a cheap shift. A partition is the unit of placement on the ring; keys
+/// are never placed individually.
+pub const NUM_PARTITIONS: usize = 1 << 8;
+
+/// The number of bits of a key hash used to index a partition.
+const PARTITION_BITS: u32 = 8;
+
+/// Identifies a physical node participating in the cluster.
+pub type NodeId = u64;
+
+/// Identifies one of the `NUM_PARTITIONS` partitions the key space is split
+/// into.
+pub type PartitionId = u16;
+
+/// Describes a physical node and the weight it should carry on the ring. A
+/// node's `capacity` determines how many tokens it contributes, so larger
+/// nodes own proportionally more of the key space.
+pub struct NodeConfig {
+    pub id: NodeId,
+    pub capacity: u16,
+}
+
+// A single token on the ring. `partition_hash` is the position of the token in
+// the partition key space; the token hands the partition it falls on to its
+// `node_id`.
+struct Token {
+    partition_hash: u16,
+    node_id: NodeId,
+}
+
+/// A consistent-hashing ring that maps keys to the set of physical nodes
+/// responsible for them.
+///
+/// The ring maintains a sorted vector of `(partition_hash, node_id)` tokens
+/// built from each node's configured capacity. A key is first mapped to one of
+/// `NUM_PARTITIONS` partitions by taking the top `PARTITION_BITS` of a hash of
+/// `(tenant_id, table_id, key)`; the partition is then walked clockwise around
+/// the token list and assigned to the first `replication_factor` distinct
+/// nodes encountered.
+pub struct Ring {
+    // Tokens sorted ascending by `partition_hash`.
+    tokens: Vec<Token>,
+    replication_factor: usize,
+}
+
+impl Ring {
+    /// Builds a new ring from the given node configuration and replication
+    /// factor. Each node contributes `capacity` evenly-spaced tokens so that
+    /// the key space is divided roughly in proportion to capacity.
+    pub fn new(nodes: &[NodeConfig], replication_factor: usize) -> Ring {
+        let mut tokens: Vec<Token> = Vec::new();
+
+        for node in nodes {
+            // Spread each node's tokens evenly across the partition key space
+            // and perturb them by the node id so that different nodes do not
+            // collide on the same positions.
+            for i in 0..node.capacity {
+                let stride = (u16::max_value() as u32 + 1) / (node.capacity as u32);
+                let base = (i as u32) * stride;
+                let jitter = hash_u64(node.id).wrapping_add(i as u64) as u32;
+                let partition_hash = ((base + jitter) & 0xffff) as u16;
+                tokens.push(Token { partition_hash, node_id: node.id });
+            }
+        }
+
+        tokens.sort_by_key(|t| t.partition_hash);
+
+        Ring { tokens, replication_factor }
+    }
+
+    /// Returns the partition a key maps to. The partition is taken from the top
+    /// `PARTITION_BITS` of a hash of `(tenant_id, table_id, key)`.
+    pub fn partition_of(&self, tenant_id: UserId, table_id: TableId, key: &[u8]) -> PartitionId {
+        let h = hash_key(tenant_id, table_id, key);
+        (h >> (64 - PARTITION_BITS)) as PartitionId
+    }
+
+    /// Returns the ordered set of nodes responsible for a partition. The first
+    /// entry is the primary; the remainder are replicas in clockwise order.
+    /// The returned vector holds at most `replication_factor` distinct nodes.
+    pub fn nodes_for(&self, partition: PartitionId) -> Vec<NodeId> {
+        let mut nodes: Vec<NodeId> = Vec::with_capacity(self.replication_factor);
+
+        if self.tokens.is_empty() {
+            return nodes;
+        }
+
+        // Scale the partition index up to the token key space and find the
+        // first token at or clockwise of that position.
+        let pos = (partition as u32) << (16 - PARTITION_BITS);
+        let start = self
+            .tokens
+            .binary_search_by(|t| (t.partition_hash as u32).cmp(&pos))
+            .unwrap_or_else(|i| i);
+
+        // Walk clockwise, wrapping around the ring, collecting distinct nodes
+        // until the replication factor is met.
+        for offset in 0..self.tokens.len() {
+            let token = &self.tokens[(start + offset) % self.tokens.len()];
+            if !nodes.contains(&token.node_id) {
+                nodes.push(token.node_id);
+                if nodes.len() == self.replication_factor {
+                    break;
+                }
+            }
+        }
+
+        nodes
+    }
+
+    /// The number of replicas each partition is placed on.
+    pub fn replication_factor(&self) -> usize {
+        self.replication_factor
+    }
+}
+
+// Hashes a `(tenant_id, table_id, key)` tuple into the full 64-bit key space.
+fn hash_key(tenant_id: UserId, table_id: TableId, key: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    tenant_id.hash(&mut hasher);
+    table_id.hash(&mut hasher);
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+// Hashes a single integer, used to perturb a node's token positions.
+fn hash_u64(value: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_node_owns_every_partition() {
+        let ring = Ring::new(&[NodeConfig { id: 1, capacity: 8 }], 3);
+        for partition in 0..NUM_PARTITIONS as PartitionId {
+            assert_eq!(ring.nodes_for(partition), vec![1]);
+        }
+    }
+
+    #[test]
+    fn replica_set_is_distinct_and_sized() {
+        let nodes = vec![
+            NodeConfig { id: 1, capacity: 16 },
+            NodeConfig { id: 2, capacity: 16 },
+            NodeConfig { id: 3, capacity: 16 },
+            NodeConfig { id: 4, capacity: 16 },
+        ];
+        let ring = Ring::new(&nodes, 3);
+        for partition in 0..NUM_PARTITIONS as PartitionId {
+            let replicas = ring.nodes_for(partition);
+            assert_eq!(replicas.len(), 3, "should place on replication_factor nodes");
+
+            let mut distinct = replicas.clone();
+            distinct.sort();
+            distinct.dedup();
+            assert_eq!(distinct.len(), replicas.len(), "replicas must be distinct");
+        }
+    }
+
+    #[test]
+    fn empty_ring_returns_no_nodes() {
+        let ring = Ring::new(&[], 3);
+        assert!(ring.nodes_for(0).is_empty());
+    }
+
+    #[test]
+    fn partition_index_is_in_range() {
+        let ring = Ring::new(&[NodeConfig { id: 1, capacity: 4 }], 1);
+        let partition = ring.partition_of(1, 1, b"some-key");
+        assert!((partition as usize) < NUM_PARTITIONS);
+    }
+
+    #[test]
+    fn placement_is_stable_for_the_same_key() {
+        let ring = Ring::new(&[NodeConfig { id: 1, capacity: 4 }], 1);
+        let first = ring.partition_of(7, 3, b"stable");
+        let second = ring.partition_of(7, 3, b"stable");
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,297 @@
+use std::mem::size_of;
+
+use e2d2::headers::{EndOffset, UdpHeader};
+
+/// The opcode carried on every RPC request, identifying the operation the
+/// service should perform. `InvalidOperation` is the catch-all returned by
+/// `parse_rpc_opcode` for a byte that maps to no known operation.
+#[repr(u8)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum OpCode {
+    /// Invoke a stored procedure extension.
+    SandstormInvokeRpc = 0x01,
+    /// Look up a single key.
+    SandstormGetRpc = 0x02,
+    /// Store a single key-value pair.
+    SandstormPutRpc = 0x03,
+    /// Look up a batch of keys in a single request.
+    SandstormMultiGetRpc = 0x04,
+    /// The request carried an opcode this service does not implement.
+    InvalidOperation = 0xff,
+}
+
+/// The status written into the common header of every response, reporting the
+/// outcome of the RPC to the client.
+#[repr(u8)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RpcStatus {
+    /// The operation completed successfully.
+    StatusOk = 0x01,
+    /// The named tenant does not exist.
+    StatusTenantDoesNotExist = 0x02,
+    /// The named table does not exist.
+    StatusTableDoesNotExist = 0x03,
+    /// The named object does not exist.
+    StatusObjectDoesNotExist = 0x04,
+    /// The request could not be parsed or violated the wireformat.
+    StatusMalformedRequest = 0x05,
+    /// The server failed to service an otherwise valid request.
+    StatusInternalError = 0x06,
+    /// Fewer than a read/write quorum of replicas acknowledged in time. The
+    /// header's `quorum_need`/`quorum_got`/`quorum_total` fields carry the
+    /// observed counts so a transient degradation can be told apart from a
+    /// permanent failure.
+    StatusQuorumUnavailable = 0x07,
+    /// The request carried an opcode the service does not implement.
+    StatusUnknownOpcode = 0x08,
+    /// A stored value's checksum did not match its bytes on read, or a
+    /// client-supplied checksum did not match the value on write.
+    StatusChecksumMismatch = 0x09,
+}
+
+/// The header common to every request and response. It names the operation and
+/// issuing tenant and, on responses, carries the status and the quorum counts
+/// observed while servicing the RPC.
+#[repr(C, packed)]
+pub struct CommonHeader {
+    pub opcode: OpCode,
+    pub status: RpcStatus,
+    pub tenant: u32,
+    /// Acknowledgements required to form a quorum for this operation.
+    pub quorum_need: u16,
+    /// Acknowledgements actually collected from the replica set.
+    pub quorum_got: u16,
+    /// Replicas the operation was issued to.
+    pub quorum_total: u16,
+}
+
+impl CommonHeader {
+    fn new(opcode: OpCode) -> CommonHeader {
+        CommonHeader {
+            opcode,
+            status: RpcStatus::StatusOk,
+            tenant: 0,
+            quorum_need: 0,
+            quorum_got: 0,
+            quorum_total: 0,
+        }
+    }
+}
+
+// Implements the e2d2 `EndOffset` trait for a fixed-size header whose preceding
+// header on the wire is the UDP header. Every Sandstorm RPC header has this
+// shape, so the boilerplate is shared through a macro.
+macro_rules! impl_end_offset {
+    ($header:ty) => {
+        impl EndOffset for $header {
+            type PreviousHeader = UdpHeader;
+
+            fn offset(&self) -> usize {
+                size_of::<$header>()
+            }
+
+            fn size() -> usize {
+                size_of::<$header>()
+            }
+
+            fn payload_size(&self, hint: usize) -> usize {
+                hint - size_of::<$header>()
+            }
+
+            fn check_correct(&self, _prev: &UdpHeader) -> bool {
+                true
+            }
+        }
+    };
+}
+
+/// The header of a Get() request: the table to read from and the length of the
+/// key that follows in the payload.
+#[repr(C, packed)]
+pub struct GetRequest {
+    pub common_header: CommonHeader,
+    pub table_id: u64,
+    pub key_length: u16,
+    /// The checksum algorithm the client expects the value to be verified with,
+    /// or `0` for none. See `checksum::ALGORITHM_CRC32C`.
+    pub checksum_algorithm: u8,
+}
+
+impl GetRequest {
+    pub fn new(tenant: u32, table_id: u64, key_length: u16) -> GetRequest {
+        let mut common_header = CommonHeader::new(OpCode::SandstormGetRpc);
+        common_header.tenant = tenant;
+        GetRequest {
+            common_header,
+            table_id,
+            key_length,
+            checksum_algorithm: 0,
+        }
+    }
+}
+
+/// The header of a Get() response: the length of the value written into the
+/// payload and the checksum the client can use to validate it end-to-end.
+#[repr(C, packed)]
+pub struct GetResponse {
+    pub common_header: CommonHeader,
+    pub value_length: u32,
+    /// CRC32C of the returned value, for end-to-end validation by the client.
+    pub checksum: u32,
+    /// The algorithm `checksum` was computed with. See `checksum::ALGORITHM_CRC32C`.
+    pub checksum_algorithm: u8,
+}
+
+impl GetResponse {
+    pub fn new() -> GetResponse {
+        GetResponse {
+            common_header: CommonHeader::new(OpCode::SandstormGetRpc),
+            value_length: 0,
+            checksum: 0,
+            checksum_algorithm: 0,
+        }
+    }
+}
+
+/// The header of a Put() request: the table to write to, the key and value
+/// lengths that follow in the payload, and an optional client-supplied
+/// checksum over the value so integrity is preserved across the whole path.
+#[repr(C, packed)]
+pub struct PutRequest {
+    pub common_header: CommonHeader,
+    pub table_id: u64,
+    pub key_length: u16,
+    pub value_length: u32,
+    /// Client-supplied CRC32C over the value, verified before the value is
+    /// stored. Ignored when `checksum_algorithm` is `0`.
+    pub checksum: u32,
+    pub checksum_algorithm: u8,
+}
+
+impl PutRequest {
+    pub fn new(tenant: u32, table_id: u64, key_length: u16, value_length: u32) -> PutRequest {
+        let mut common_header = CommonHeader::new(OpCode::SandstormPutRpc);
+        common_header.tenant = tenant;
+        PutRequest {
+            common_header,
+            table_id,
+            key_length,
+            value_length,
+            checksum: 0,
+            checksum_algorithm: 0,
+        }
+    }
+}
+
+/// The header of a Put() response: the outcome is carried entirely in the
+/// common header.
+#[repr(C, packed)]
+pub struct PutResponse {
+    pub common_header: CommonHeader,
+}
+
+impl PutResponse {
+    pub fn new() -> PutResponse {
+        PutResponse {
+            common_header: CommonHeader::new(OpCode::SandstormPutRpc),
+        }
+    }
+}
+
+/// The header of a MultiGet() request: the number of `(table_id, key_length,
+/// key)` tuples packed into the payload.
+#[repr(C, packed)]
+pub struct MultiGetRequest {
+    pub common_header: CommonHeader,
+    pub num_keys: u32,
+}
+
+impl MultiGetRequest {
+    pub fn new(tenant: u32, num_keys: u32) -> MultiGetRequest {
+        let mut common_header = CommonHeader::new(OpCode::SandstormMultiGetRpc);
+        common_header.tenant = tenant;
+        MultiGetRequest {
+            common_header,
+            num_keys,
+        }
+    }
+}
+
+/// The header of a MultiGet() response: the number of per-key results written,
+/// length-delimited, into the payload.
+#[repr(C, packed)]
+pub struct MultiGetResponse {
+    pub common_header: CommonHeader,
+    pub num_keys: u32,
+}
+
+impl MultiGetResponse {
+    pub fn new() -> MultiGetResponse {
+        MultiGetResponse {
+            common_header: CommonHeader::new(OpCode::SandstormMultiGetRpc),
+            num_keys: 0,
+        }
+    }
+}
+
+/// The header of an Invoke() request: the lengths of the extension name and the
+/// argument blob that follow in the payload.
+#[repr(C, packed)]
+pub struct InvokeRequest {
+    pub common_header: CommonHeader,
+    pub name_length: u32,
+    pub args_length: u32,
+}
+
+impl InvokeRequest {
+    pub fn new(tenant: u32, name_length: u32, args_length: u32) -> InvokeRequest {
+        let mut common_header = CommonHeader::new(OpCode::SandstormInvokeRpc);
+        common_header.tenant = tenant;
+        InvokeRequest {
+            common_header,
+            name_length,
+            args_length,
+        }
+    }
+}
+
+/// The header of an Invoke() response.
+#[repr(C, packed)]
+pub struct InvokeResponse {
+    pub common_header: CommonHeader,
+}
+
+impl InvokeResponse {
+    pub fn new() -> InvokeResponse {
+        InvokeResponse {
+            common_header: CommonHeader::new(OpCode::SandstormInvokeRpc),
+        }
+    }
+}
+
+/// A minimal response carrying only the common header. It is pushed onto a raw
+/// packet so that any dispatch outcome — in particular an unimplemented opcode
+/// — can be answered with a well-formed, status-bearing response rather than a
+/// packet with an uninitialized header.
+#[repr(C, packed)]
+pub struct CommonResponse {
+    pub common_header: CommonHeader,
+}
+
+impl CommonResponse {
+    pub fn new() -> CommonResponse {
+        CommonResponse {
+            common_header: CommonHeader::new(OpCode::InvalidOperation),
+        }
+    }
+}
+
+impl_end_offset!(GetRequest);
+impl_end_offset!(GetResponse);
+impl_end_offset!(PutRequest);
+impl_end_offset!(PutResponse);
+impl_end_offset!(MultiGetRequest);
+impl_end_offset!(MultiGetResponse);
+impl_end_offset!(InvokeRequest);
+impl_end_offset!(InvokeResponse);
+impl_end_offset!(CommonResponse);